@@ -0,0 +1,71 @@
+//! Decoding QR codes from images — the reverse direction of code
+//! generation — so the app can round-trip data instead of only generating
+//! codes. The actual worker lives behind a native/wasm split, mirroring
+//! [`crate::GenerationBackend`], since `std::thread::spawn` isn't available
+//! on `wasm32-unknown-unknown`.
+#[cfg_attr(not(target_arch = "wasm32"), path = "decode_native.rs")]
+#[cfg_attr(target_arch = "wasm32", path = "decode_wasm.rs")]
+mod decode_backend;
+
+pub use decode_backend::DecodeThreadCommunicator;
+
+use crossbeam_channel::Sender;
+use image::{ImageBuffer, Luma};
+use slint::{EventLoopError, Rgba8Pixel, SharedPixelBuffer};
+
+#[derive(Debug)]
+pub struct QrDecodeRequest {
+    pub image: SharedPixelBuffer<Rgba8Pixel>,
+}
+
+pub type DecodeResult = Result<String, String>;
+pub type DynDecodeCallbackFn = dyn Fn(DecodeResult) -> Result<(), EventLoopError> + Send;
+
+/// A decode backend that a [`QrDecodeRequest`] is sent to and a
+/// [`DecodeResult`] comes back from, mirroring [`crate::GenerationBackend`]:
+/// native builds decode on an OS thread, `wasm32` builds on the local
+/// executor. Both use the same sender type so `main`'s wiring doesn't need
+/// to know which one it's using.
+pub trait DecodeBackend: Sized {
+    fn new_thread(callback_function: Box<DynDecodeCallbackFn>) -> Self;
+
+    /// Return will be valid until `self` is dropped or `stop_sender()` is called.
+    fn get_weak_sender(&self) -> std::rc::Weak<Sender<QrDecodeRequest>>;
+
+    fn stop_sender(self);
+}
+
+/// Converts an RGBA buffer to grayscale, locates finder patterns, and
+/// returns the text payload of the first QR code found.
+pub fn decode_qr_image(buffer: &SharedPixelBuffer<Rgba8Pixel>) -> DecodeResult {
+    let width = buffer.width();
+    let height = buffer.height();
+    let pixels = buffer.as_slice();
+
+    let luma = ImageBuffer::<Luma<u8>, Vec<u8>>::from_fn(width, height, |x, y| {
+        let px = pixels[(y * width + x) as usize];
+        let gray = (0.299 * px.r as f32 + 0.587 * px.g as f32 + 0.114 * px.b as f32) as u8;
+        Luma([gray])
+    });
+
+    let mut prepared = rqrr::PreparedImage::prepare(luma);
+    let grids = prepared.detect_grids();
+
+    let grid = grids
+        .first()
+        .ok_or_else(|| "No QR code found in image".to_string())?;
+
+    let (_meta, content) = grid.decode().map_err(|err| err.to_string())?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_qr_code_is_reported_as_error() {
+        let blank = SharedPixelBuffer::<Rgba8Pixel>::new(16, 16);
+        assert!(decode_qr_image(&blank).is_err());
+    }
+}
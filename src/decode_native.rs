@@ -0,0 +1,75 @@
+//! Native decode backend: runs decoding on a regular OS thread, the same
+//! way the native generation backend (`native.rs`) handles generation.
+use std::rc::Rc;
+use std::thread::{self, JoinHandle};
+
+use crossbeam_channel::{bounded, Receiver, Sender};
+use log::error;
+
+use crate::decode::{decode_qr_image, DecodeBackend, DynDecodeCallbackFn, QrDecodeRequest};
+use crate::panic_message;
+
+#[derive(Debug)]
+pub struct DecodeThreadCommunicator {
+    handle: JoinHandle<()>,
+    sender: Rc<Sender<QrDecodeRequest>>,
+}
+
+impl DecodeBackend for DecodeThreadCommunicator {
+    fn new_thread(callback_function: Box<DynDecodeCallbackFn>) -> Self {
+        let (tx, rx) = bounded(1);
+        let handle = DecodeThread::spawn(rx, callback_function);
+        DecodeThreadCommunicator {
+            handle,
+            sender: Rc::new(tx),
+        }
+    }
+
+    fn get_weak_sender(&self) -> std::rc::Weak<Sender<QrDecodeRequest>> {
+        Rc::downgrade(&self.sender)
+    }
+
+    fn stop_sender(self) {
+        drop(self.sender);
+        if let Err(panic) = self.handle.join() {
+            error!(
+                "Decode thread panicked before joining: {}",
+                panic_message(&*panic)
+            );
+        }
+    }
+}
+
+struct DecodeThread {
+    receiver: Receiver<QrDecodeRequest>,
+    result_callback: Box<DynDecodeCallbackFn>,
+}
+
+impl DecodeThread {
+    fn spawn(
+        rx: Receiver<QrDecodeRequest>,
+        callback_function: Box<DynDecodeCallbackFn>,
+    ) -> JoinHandle<()> {
+        thread::spawn(|| {
+            DecodeThread {
+                receiver: rx,
+                result_callback: callback_function,
+            }
+            .work_when_available();
+        })
+    }
+
+    fn work_when_available(self) {
+        while let Ok(mut decode_req) = self.receiver.recv() {
+            // Get latest, throw out old
+            while let Ok(new_request) = self.receiver.try_recv() {
+                decode_req = new_request
+            }
+
+            let result = decode_qr_image(&decode_req.image);
+            if let Err(e) = (*self.result_callback)(result) {
+                error!("Error while calling decode callback: {}", e.to_string());
+            }
+        }
+    }
+}
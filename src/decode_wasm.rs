@@ -0,0 +1,51 @@
+//! `wasm32` decode backend: `std::thread::spawn` isn't available on this
+//! target, so decoding runs on the local executor via [`spawn_local`]
+//! instead of a dedicated worker. `decode_qr_image` is cheap enough per
+//! frame that it doesn't need a Web Worker the way generation does.
+use std::rc::Rc;
+
+use crossbeam_channel::{bounded, Sender, TryRecvError};
+use log::error;
+use wasm_bindgen_futures::spawn_local;
+
+use crate::decode::{decode_qr_image, DecodeBackend, DynDecodeCallbackFn, QrDecodeRequest};
+
+#[derive(Debug)]
+pub struct DecodeThreadCommunicator {
+    sender: Rc<Sender<QrDecodeRequest>>,
+}
+
+impl DecodeBackend for DecodeThreadCommunicator {
+    fn new_thread(callback_function: Box<DynDecodeCallbackFn>) -> Self {
+        let (tx, rx) = bounded::<QrDecodeRequest>(1);
+
+        spawn_local(async move {
+            loop {
+                match rx.try_recv() {
+                    Ok(decode_req) => {
+                        let result = decode_qr_image(&decode_req.image);
+                        if let Err(e) = (*callback_function)(result) {
+                            error!("Error while calling decode callback: {}", e.to_string());
+                        }
+                    }
+                    Err(TryRecvError::Disconnected) => break,
+                    Err(TryRecvError::Empty) => {}
+                }
+                gloo_timers::future::TimeoutFuture::new(0).await;
+            }
+        });
+
+        DecodeThreadCommunicator {
+            sender: Rc::new(tx),
+        }
+    }
+
+    /// Return will be valid until `self` is dropped or `stop_sender()` is called.
+    fn get_weak_sender(&self) -> std::rc::Weak<Sender<QrDecodeRequest>> {
+        Rc::downgrade(&self.sender)
+    }
+
+    fn stop_sender(self) {
+        drop(self.sender);
+    }
+}
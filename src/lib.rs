@@ -1,99 +1,116 @@
 //! Backend for qr code generation in a second thread
-use std::rc::Rc;
-use std::sync::mpsc::{self, Receiver, Sender};
-use std::thread::{self, JoinHandle};
+mod decode;
+mod export;
+mod stream;
 
+#[cfg_attr(not(target_arch = "wasm32"), path = "native.rs")]
+#[cfg_attr(target_arch = "wasm32", path = "wasm.rs")]
+mod backend;
+
+/// The Web Worker-side counterpart of `backend` on `wasm32`: exports the
+/// `wasm_bindgen` entry point `qr_generation_worker.js` calls into.
+#[cfg(target_arch = "wasm32")]
+mod worker_entry;
+
+pub use backend::BackgroundThreadCommunicator;
+pub use decode::{DecodeBackend, DecodeResult, DecodeThreadCommunicator, QrDecodeRequest};
+pub use export::{export_qr, ExportFormat};
+
+use crossbeam_channel::Sender;
 use fast_qr::convert::image::ImageBuilder;
 use fast_qr::convert::{Builder, Shape};
 use fast_qr::qr::QRBuilder;
 use fast_qr::{QRCode, ECL};
-use log::error;
 use slint::{EventLoopError, Rgba8Pixel, SharedPixelBuffer};
 
+/// What a [`QrGenerationRequest`] should render: a single static code, or an
+/// animated sequence of frames for payloads too large for one symbol.
+#[derive(Debug, Clone)]
+pub enum Content {
+    Static(String),
+    Streamed {
+        data: Vec<u8>,
+        chunk_size: usize,
+        fps: u32,
+    },
+}
+
 #[derive(Debug)]
 pub struct QrGenerationRequest {
-    pub data: String,
+    pub content: Content,
     pub correction_level: Option<ECL>,
+    /// Side length in pixels to render the code at. `None` falls back to
+    /// `fast_qr`'s default module size, which looks blurry once the UI
+    /// scales it up to fill the available display area.
+    pub target_px: Option<u32>,
 }
 
-#[derive(Debug)]
-pub struct BackgroundThreadCommunicator {
-    handle: JoinHandle<()>,
-    sender: Rc<Sender<QrGenerationRequest>>,
+/// Out-of-band signal for the generation worker, separate from generation
+/// requests so it can interrupt or stop the worker without waiting for a
+/// new request (or the current stream) to come through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlMessage {
+    Pause,
+    Resume,
+    Cancel,
+    Shutdown,
 }
 
 pub type PixelMapResult = Result<SharedPixelBuffer<Rgba8Pixel>, String>;
 pub type DynPixmapCallbackFn = dyn Fn(PixelMapResult) -> Result<(), EventLoopError> + Send;
 
-impl BackgroundThreadCommunicator {
-    pub fn new_thread(callback_function: Box<DynPixmapCallbackFn>) -> Self {
-        let (tx, rx) = mpsc::channel();
-        let handle = BackgroundThread::spawn(rx, callback_function);
-        BackgroundThreadCommunicator {
-            handle,
-            sender: Rc::new(tx),
-        }
-    }
+/// A generation backend that a [`QrGenerationRequest`] is sent to and a
+/// [`PixelMapResult`] comes back from. Native builds run this on an OS
+/// thread; `wasm32` builds can't spawn one (`std::thread::spawn` and
+/// `JoinHandle` don't exist there), so they drive the same requests through
+/// a Web Worker instead. Both keep the sender/request/result types
+/// identical so `main`'s wiring doesn't need to know which one it's using.
+pub trait GenerationBackend: Sized {
+    fn new_thread(callback_function: Box<DynPixmapCallbackFn>) -> Self;
 
-    /// Return will be valid until `self` is dropped or `stop_sender()` is called.
-    pub fn get_weak_sender(&self) -> std::rc::Weak<Sender<QrGenerationRequest>> {
-        Rc::downgrade(&self.sender)
-    }
+    /// Return will be valid until `self` is dropped or `stop()` is called.
+    fn get_weak_sender(&self) -> std::rc::Weak<Sender<QrGenerationRequest>>;
 
-    pub fn stop_sender(self) -> JoinHandle<()> {
-        drop(self.sender);
-        self.handle
-    }
-}
+    /// Return will be valid until `self` is dropped or `stop()` is called.
+    fn get_weak_control_sender(&self) -> std::rc::Weak<Sender<ControlMessage>>;
 
-struct BackgroundThread {
-    receiver: Receiver<QrGenerationRequest>,
-    display_callback: Box<DynPixmapCallbackFn>,
+    fn stop(self);
 }
 
-impl BackgroundThread {
-    fn spawn(
-        rx: Receiver<QrGenerationRequest>,
-        callback_function: Box<DynPixmapCallbackFn>,
-    ) -> JoinHandle<()> {
-        thread::spawn(|| {
-            BackgroundThread {
-                receiver: rx,
-                display_callback: callback_function,
-            }
-            .work_when_available();
-        })
+/// Renders `code` to a pixmap, fitting it to `target_px` on each side when
+/// given so the image is crisp at whatever resolution the UI is showing it
+/// at, rather than relying on the UI to upscale `fast_qr`'s default size.
+fn render_pixmap(code: QRCode, target_px: Option<u32>) -> SharedPixelBuffer<Rgba8Pixel> {
+    let mut builder = ImageBuilder::default();
+    builder.shape(Shape::Square);
+    if let Some(target_px) = target_px {
+        builder.fit_width(target_px).fit_height(target_px);
     }
+    let pixmap = builder.to_pixmap(&code);
+    SharedPixelBuffer::clone_from_slice(pixmap.data(), pixmap.width(), pixmap.height())
+}
 
-    fn work_when_available(self) {
-        while let Ok(mut qr_gen_req) = self.receiver.recv() {
-            // Get latest, throw out old
-            while let Ok(new_request) = self.receiver.try_recv() {
-                qr_gen_req = new_request
-            }
-            let image_result = new_qr_code_image(qr_gen_req).map(|code| {
-                ImageBuilder::default()
-                    .shape(Shape::Square)
-                    // .background_color([255, 255, 255, 0])
-                    .to_pixmap(&code)
-            });
-
-            // Convert Pixmap to SharedPixelBuffer
-            let pix_buffer_result = image_result.map(|pixmap| {
-                SharedPixelBuffer::clone_from_slice(pixmap.data(), pixmap.width(), pixmap.height())
-            });
-
-            // Call callback. If it returns an error, log it.
-            if let Err(e) = (*self.display_callback)(pix_buffer_result) {
-                error!("Error while calling display callback: {}", e.to_string());
-            };
-        }
-    }
+/// Extracts a human-readable message from a [`std::thread::Result`]'s panic
+/// payload, falling back to a generic description for payloads that aren't
+/// the usual `&str`/`String` passed to `panic!`.
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
 }
 
-fn new_qr_code_image(settings: QrGenerationRequest) -> Result<QRCode, String> {
-    let mut builder = QRBuilder::new(settings.data);
-    if let Some(correction_level) = settings.correction_level {
+fn new_qr_code_image(content: Content, correction_level: Option<ECL>) -> Result<QRCode, String> {
+    let data = match content {
+        Content::Static(data) => data,
+        Content::Streamed { .. } => {
+            return Err("streamed content must be split into frames before encoding".to_string())
+        }
+    };
+
+    let mut builder = QRBuilder::new(data);
+    if let Some(correction_level) = correction_level {
         builder.ecl(correction_level);
     }
 
@@ -120,18 +137,15 @@ mod tests {
             .upgrade()
             .expect("Error while upgrading weak sender")
             .send(QrGenerationRequest {
-                data: string,
+                content: Content::Static(string),
                 correction_level: None,
+                target_px: None,
             })
             .expect("Error while sending!");
 
-        let join_handle = bt_comm.stop_sender();
+        bt_comm.stop();
 
         // Sender should be dropped
         assert!(weak_sender.upgrade().is_none());
-
-        join_handle
-            .join()
-            .expect("Background thread panicked before joining!");
     }
 }
@@ -1,8 +1,16 @@
 //! Runs the application and handles communication between the backend and ui.
-use std::{rc, sync::mpsc::Sender};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::rc;
 
+use crossbeam_channel::Sender as CrossbeamSender;
 use log::*;
-use qr_app::{BackgroundThreadCommunicator, PixelMapResult, QrGenerationRequest};
+use qr_app::{
+    export_qr, BackgroundThreadCommunicator, Content, DecodeBackend, DecodeResult,
+    DecodeThreadCommunicator, ExportFormat as QrExportFormat, GenerationBackend, PixelMapResult,
+    QrDecodeRequest, QrGenerationRequest,
+};
 use slint::{ComponentHandle, Image};
 
 slint::include_modules!();
@@ -22,31 +30,161 @@ fn start_application() {
         window_weak.upgrade_in_event_loop(|window: MainWindow| window.update_image(pixels))
     }));
 
-    // Sets the callback for generating a new qr code
+    // Remembers the last requested data/error-correction so a resize can
+    // retrigger generation without the UI having to resend them.
+    let last_request = Rc::new(RefCell::new(None::<(slint::SharedString, EcLevel)>));
+
+    // Sets the callback for generating a new qr code. The window is
+    // re-queried for its current size on every call so the code is always
+    // rendered crisply at the resolution it's actually shown at.
+    let weak_sender = thread_com.get_weak_sender();
+    let window_weak = window.as_weak();
+    let last_request_for_generate = last_request.clone();
+    global_callbacks.on_generate_qr_code(move |str, ec| {
+        *last_request_for_generate.borrow_mut() = Some((str.clone(), ec));
+        let target_px = window_weak.upgrade().map(|window| qr_target_px(&window));
+        generate_qr_code(weak_sender.clone(), str, ec, target_px);
+    });
+
+    // Regenerate at the new size whenever the window is resized
+    let weak_sender = thread_com.get_weak_sender();
+    let window_weak = window.as_weak();
+    let last_request_for_resize = last_request.clone();
+    window.window().on_size_changed(move |_| {
+        let Some(window) = window_weak.upgrade() else {
+            return;
+        };
+        let Some((str, ec)) = last_request_for_resize.borrow().clone() else {
+            return;
+        };
+        let target_px = qr_target_px(&window);
+        regenerate_on_resize(weak_sender.clone(), str, ec, target_px);
+    });
+
+    // Sets the callback for exporting the last generated code to a file
+    let window_weak = window.as_weak();
+    let last_request_for_export = last_request.clone();
+    global_callbacks.on_export_qr(move |path, format| {
+        export_qr_code(&window_weak, &last_request_for_export, path, format)
+    });
+
+    // Sets the callback for generating an animated/streamed qr code, for
+    // payloads too large to fit in a single static code
     let weak_sender = thread_com.get_weak_sender();
+    let window_weak = window.as_weak();
+    global_callbacks.on_generate_stream_qr_code(move |str, chunk_size, fps, ec| {
+        let target_px = window_weak.upgrade().map(|window| qr_target_px(&window));
+        generate_streamed_qr_code(weak_sender.clone(), str, chunk_size, fps, ec, target_px);
+    });
+
+    // Creates a new decode thread worker with a callback function to report the decoded text
+    let window_weak = window.as_weak();
+    let decode_com = DecodeThreadCommunicator::new_thread(Box::new(move |result| {
+        window_weak.upgrade_in_event_loop(|window: MainWindow| window.update_decoded_text(result))
+    }));
+
+    // Sets the callback for decoding a scanned or loaded image
+    let weak_decode_sender = decode_com.get_weak_sender();
     global_callbacks
-        .on_generate_qr_code(move |str, ec| generate_qr_code(weak_sender.clone(), str, ec));
+        .on_decode_image(move |image| decode_image(weak_decode_sender.clone(), image));
 
     // Run window
     window.run().unwrap();
     // Clean up
-    let _ = thread_com.stop_sender().join();
+    thread_com.stop();
+    decode_com.stop_sender();
+}
+
+/// Fraction of the window's shorter side reserved for displaying the QR
+/// code, leaving the rest of that side for surrounding text and controls.
+const QR_AREA_FRACTION: f32 = 0.8;
+
+/// Picks the pixel size to render the QR code at so it fills its share of
+/// the window without being blurrily scaled up by the UI afterwards.
+fn qr_target_px(window: &MainWindow) -> u32 {
+    let size = window.window().size();
+    let side = size.width.min(size.height) as f32;
+    (side * QR_AREA_FRACTION) as u32
 }
 
 /// Generates the qr code by sending the information to the background thread
 fn generate_qr_code(
-    weak_sender: rc::Weak<Sender<QrGenerationRequest>>,
+    weak_sender: rc::Weak<CrossbeamSender<QrGenerationRequest>>,
+    shared_data: slint::SharedString,
+    ec_level: EcLevel,
+    target_px: Option<u32>,
+) {
+    let data = shared_data.as_str().to_string();
+    let ecl = ec_level.to_qr_enum();
+
+    if let Some(sender) = weak_sender.upgrade() {
+        // Set to background thread. If send errors, logs it
+        if let Err(e) = sender.send(QrGenerationRequest {
+            content: Content::Static(data),
+            correction_level: ecl,
+            target_px,
+        }) {
+            error!("Send error while sending: {}", e.to_string())
+        }
+    } else {
+        // Sender already dropped
+        warn!("Tried sending but thread sender doesn't exist!")
+    }
+}
+
+/// Re-requests generation at a new size while a drag-resize is in
+/// progress. Unlike [`generate_qr_code`], this uses `try_send` on the
+/// capacity-1 request channel and drops the request rather than blocking:
+/// a resize can fire many times per frame, and blocking here would stall
+/// the UI event loop until the background thread drains the previous
+/// request. A dropped request is not retried; only a later resize event
+/// sends a new request, at the size current at that point.
+fn regenerate_on_resize(
+    weak_sender: rc::Weak<CrossbeamSender<QrGenerationRequest>>,
     shared_data: slint::SharedString,
     ec_level: EcLevel,
+    target_px: u32,
 ) {
     let data = shared_data.as_str().to_string();
     let ecl = ec_level.to_qr_enum();
 
+    if let Some(sender) = weak_sender.upgrade() {
+        if let Err(e) = sender.try_send(QrGenerationRequest {
+            content: Content::Static(data),
+            correction_level: ecl,
+            target_px: Some(target_px),
+        }) {
+            debug!("Dropped resize-triggered regeneration: {}", e.to_string())
+        }
+    } else {
+        // Sender already dropped
+        warn!("Tried sending but thread sender doesn't exist!")
+    }
+}
+
+/// Generates an animated QR stream for a payload too large for a single
+/// code, by sending it to the background thread as `Content::Streamed`
+fn generate_streamed_qr_code(
+    weak_sender: rc::Weak<CrossbeamSender<QrGenerationRequest>>,
+    shared_data: slint::SharedString,
+    chunk_size: i32,
+    fps: i32,
+    ec_level: EcLevel,
+    target_px: Option<u32>,
+) {
+    let data = shared_data.as_str().as_bytes().to_vec();
+    let ecl = ec_level.to_qr_enum();
+
     if let Some(sender) = weak_sender.upgrade() {
         // Set to background thread. If send errors, logs it
         if let Err(e) = sender.send(QrGenerationRequest {
-            data,
+            content: Content::Streamed {
+                data,
+                chunk_size: chunk_size.max(1) as usize,
+                fps: fps.max(1) as u32,
+            },
             correction_level: ecl,
+            target_px,
         }) {
             error!("Send error while sending: {}", e.to_string())
         }
@@ -56,6 +194,59 @@ fn generate_qr_code(
     }
 }
 
+/// Exports the last generated code to `path` in the requested format,
+/// surfacing a failure through the existing `ImageStatus` state. A
+/// successful export leaves that state unchanged.
+fn export_qr_code(
+    window_weak: &slint::Weak<MainWindow>,
+    last_request: &Rc<RefCell<Option<(slint::SharedString, EcLevel)>>>,
+    path: slint::SharedString,
+    format: ExportFormat,
+) {
+    let Some(window) = window_weak.upgrade() else {
+        return;
+    };
+
+    let Some((data, ec_level)) = last_request.borrow().clone() else {
+        window.update_image(Err("Nothing to export yet".to_string()));
+        return;
+    };
+
+    let result = export_qr(
+        data.as_str().to_string(),
+        ec_level.to_qr_enum(),
+        format.to_qr_format(),
+        Path::new(path.as_str()),
+    );
+
+    if let Err(err) = result {
+        window.update_image(Err(err));
+    }
+}
+
+/// Decodes an image, either loaded from a file or captured live, by sending
+/// it to the decode background thread
+fn decode_image(
+    weak_sender: rc::Weak<CrossbeamSender<QrDecodeRequest>>,
+    image: slint::Image,
+) {
+    let Some(pixel_buffer) = image.to_rgba8() else {
+        warn!("Tried decoding an image with no pixel data");
+        return;
+    };
+
+    if let Some(sender) = weak_sender.upgrade() {
+        if let Err(e) = sender.send(QrDecodeRequest {
+            image: pixel_buffer,
+        }) {
+            error!("Send error while sending: {}", e.to_string())
+        }
+    } else {
+        // Sender already dropped
+        warn!("Tried sending but decode thread sender doesn't exist!")
+    }
+}
+
 impl MainWindow {
     /// Receives the image data or error and displays it
     fn update_image(&self, pixmap_result: PixelMapResult) {
@@ -72,6 +263,32 @@ impl MainWindow {
             }
         };
     }
+
+    /// Receives the decoded text or error and shows it in the scanner panel
+    fn update_decoded_text(&self, result: DecodeResult) {
+        let state = self.global::<State>();
+
+        match result {
+            Ok(text) => {
+                state.set_decoded_text(Into::into(text));
+                state.set_decode_status(DecodeStatus::Decoded);
+            }
+            Err(str) => {
+                state.set_decode_err_msg(Into::into(str));
+                state.set_decode_status(DecodeStatus::Error);
+            }
+        };
+    }
+}
+
+impl ExportFormat {
+    /// Converts slint's `ExportFormat` to `qr_app::ExportFormat`
+    pub fn to_qr_format(&self) -> QrExportFormat {
+        match self {
+            ExportFormat::Png => QrExportFormat::Png,
+            ExportFormat::Svg => QrExportFormat::Svg,
+        }
+    }
 }
 
 impl EcLevel {
@@ -0,0 +1,185 @@
+//! Native `GenerationBackend`: runs generation on a regular OS thread,
+//! driven by a [`crossbeam_channel::select!`] over a generation-request
+//! channel, a control channel, and (while streaming) a frame ticker.
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use crossbeam_channel::{bounded, never, select, tick, Receiver, Sender};
+use log::error;
+
+use crate::{
+    new_qr_code_image, panic_message, render_pixmap, stream, Content, ControlMessage,
+    DynPixmapCallbackFn, GenerationBackend, PixelMapResult, QrGenerationRequest,
+};
+use fast_qr::ECL;
+
+/// Monotonic id source so each streamed request gets a distinct stream id,
+/// letting a receiver tell two overlapping streams apart.
+static NEXT_STREAM_ID: AtomicU32 = AtomicU32::new(1);
+
+#[derive(Debug)]
+pub struct BackgroundThreadCommunicator {
+    handle: JoinHandle<()>,
+    request_sender: Rc<Sender<QrGenerationRequest>>,
+    control_sender: Rc<Sender<ControlMessage>>,
+}
+
+impl GenerationBackend for BackgroundThreadCommunicator {
+    fn new_thread(callback_function: Box<DynPixmapCallbackFn>) -> Self {
+        let (request_tx, request_rx) = bounded(1);
+        let (control_tx, control_rx) = bounded(16);
+        let handle = BackgroundThread::spawn(request_rx, control_rx, callback_function);
+        BackgroundThreadCommunicator {
+            handle,
+            request_sender: Rc::new(request_tx),
+            control_sender: Rc::new(control_tx),
+        }
+    }
+
+    fn get_weak_sender(&self) -> std::rc::Weak<Sender<QrGenerationRequest>> {
+        Rc::downgrade(&self.request_sender)
+    }
+
+    fn get_weak_control_sender(&self) -> std::rc::Weak<Sender<ControlMessage>> {
+        Rc::downgrade(&self.control_sender)
+    }
+
+    fn stop(self) {
+        // Shutdown is a first-class control message rather than relying on
+        // sender drop, so it's handled promptly even mid-stream.
+        let _ = self.control_sender.send(ControlMessage::Shutdown);
+        drop(self.request_sender);
+        drop(self.control_sender);
+        if let Err(panic) = self.handle.join() {
+            error!(
+                "Background generation thread panicked before joining: {}",
+                panic_message(&*panic)
+            );
+        }
+    }
+}
+
+/// The in-flight animated stream, if any: remaining frames plus the ticker
+/// that paces them.
+struct StreamState {
+    frames: std::vec::IntoIter<stream::Frame>,
+    correction_level: Option<ECL>,
+    target_px: Option<u32>,
+    ticker: Receiver<Instant>,
+}
+
+struct BackgroundThread {
+    request_receiver: Receiver<QrGenerationRequest>,
+    control_receiver: Receiver<ControlMessage>,
+    display_callback: Box<DynPixmapCallbackFn>,
+}
+
+impl BackgroundThread {
+    fn spawn(
+        request_receiver: Receiver<QrGenerationRequest>,
+        control_receiver: Receiver<ControlMessage>,
+        callback_function: Box<DynPixmapCallbackFn>,
+    ) -> JoinHandle<()> {
+        thread::spawn(|| {
+            BackgroundThread {
+                request_receiver,
+                control_receiver,
+                display_callback: callback_function,
+            }
+            .work_when_available();
+        })
+    }
+
+    fn work_when_available(self) {
+        let mut stream: Option<StreamState> = None;
+        let mut paused = false;
+
+        loop {
+            let ticker = stream
+                .as_ref()
+                .map(|s| s.ticker.clone())
+                .unwrap_or_else(never);
+
+            select! {
+                recv(self.request_receiver) -> msg => {
+                    let Ok(mut req) = msg else { break };
+                    // Get latest, throw out old
+                    while let Ok(newer) = self.request_receiver.try_recv() {
+                        req = newer;
+                    }
+                    stream = self.start_request(req);
+                }
+                recv(self.control_receiver) -> msg => {
+                    match msg {
+                        Ok(ControlMessage::Shutdown) | Err(_) => break,
+                        Ok(ControlMessage::Cancel) => stream = None,
+                        Ok(ControlMessage::Pause) => paused = true,
+                        Ok(ControlMessage::Resume) => paused = false,
+                    }
+                }
+                recv(ticker) -> _ => {
+                    if !paused {
+                        stream = self.advance_stream(stream);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders a static request immediately, or kicks off a stream and
+    /// renders its first (config) frame right away.
+    fn start_request(&self, req: QrGenerationRequest) -> Option<StreamState> {
+        match req.content {
+            Content::Static(_) => {
+                let target_px = req.target_px;
+                let result = new_qr_code_image(req.content, req.correction_level)
+                    .map(|code| render_pixmap(code, target_px));
+                self.emit(result);
+                None
+            }
+            Content::Streamed {
+                data,
+                chunk_size,
+                fps,
+            } => {
+                let stream_id = NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed);
+                let frames = match stream::split_into_frames(stream_id, &data, chunk_size) {
+                    Ok(frames) => frames.into_iter(),
+                    Err(err) => {
+                        self.emit(Err(err));
+                        return None;
+                    }
+                };
+                let ticker = tick(stream::frame_interval(fps));
+                let state = StreamState {
+                    frames,
+                    correction_level: req.correction_level,
+                    target_px: req.target_px,
+                    ticker,
+                };
+                self.advance_stream(Some(state))
+            }
+        }
+    }
+
+    /// Renders the next frame of `stream`, returning `None` once it's
+    /// exhausted so the ticker arm goes back to idling on `never()`.
+    fn advance_stream(&self, mut stream: Option<StreamState>) -> Option<StreamState> {
+        let state = stream.as_mut()?;
+        let frame = state.frames.next()?;
+
+        let result = new_qr_code_image(Content::Static(frame.text), state.correction_level)
+            .map(|code| render_pixmap(code, state.target_px));
+        self.emit(result);
+
+        stream
+    }
+
+    fn emit(&self, pix_buffer_result: PixelMapResult) {
+        if let Err(e) = (*self.display_callback)(pix_buffer_result) {
+            error!("Error while calling display callback: {}", e.to_string());
+        }
+    }
+}
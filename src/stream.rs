@@ -0,0 +1,105 @@
+//! Framing/chunking support for the "streamed" QR transfer mode, where a
+//! payload too large for a single QR symbol is cycled through as a sequence
+//! of frames (similar to file-over-QR transfer tools).
+use std::time::Duration;
+
+/// One chunk of a streamed payload, already encoded as the text that will be
+/// fed into [`fast_qr::qr::QRBuilder`].
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub index: u32,
+    pub total: u32,
+    pub text: String,
+}
+
+/// Splits `data` into a leading config frame followed by `total` chunk
+/// frames of at most `chunk_size` bytes each, every one prefixed with a
+/// small header so a receiver can reassemble the stream and detect loss.
+///
+/// Frame text layout: `stream_id:frame_index:frame_total:crc32:payload`,
+/// where `payload` is the chunk bytes hex-encoded. The config frame
+/// (`frame_index == 0`) carries no data bytes: its last field is
+/// `chunk_size` in decimal, and its `crc32` field is the CRC of the whole
+/// `data` buffer, so a receiver can size its reassembly buffer and verify
+/// the finished transfer.
+pub fn split_into_frames(
+    stream_id: u32,
+    data: &[u8],
+    chunk_size: usize,
+) -> Result<Vec<Frame>, String> {
+    if chunk_size == 0 {
+        return Err("chunk_size must be non-zero".to_string());
+    }
+
+    let chunks: Vec<&[u8]> = data.chunks(chunk_size).collect();
+    let total = chunks.len() as u32;
+
+    let config_frame = Frame {
+        index: 0,
+        total,
+        text: header(stream_id, 0, total, crc32(data)) + &format!(":{chunk_size}"),
+    };
+
+    let data_frames = chunks.into_iter().enumerate().map(|(i, chunk)| Frame {
+        index: i as u32 + 1,
+        total,
+        text: header(stream_id, i as u32 + 1, total, crc32(chunk)) + ":" + &hex_encode(chunk),
+    });
+
+    Ok(std::iter::once(config_frame).chain(data_frames).collect())
+}
+
+/// How long a frame should stay on screen for a given frame rate.
+pub fn frame_interval(fps: u32) -> Duration {
+    Duration::from_millis(1000 / fps.max(1) as u64)
+}
+
+fn header(stream_id: u32, frame_index: u32, frame_total: u32, crc: u32) -> String {
+    format!("{stream_id:08x}:{frame_index:06}:{frame_total:06}:{crc:08x}")
+}
+
+fn hex_encode(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Minimal CRC-32 (IEEE 802.3 polynomial), used only to let a receiver
+/// detect a corrupted or dropped frame, not for any cryptographic purpose.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xFFFF_FFFF;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_into_config_plus_data_frames() {
+        let data = b"hello world, this is a streamed payload".to_vec();
+        let frames = split_into_frames(1, &data, 8).expect("chunk_size is non-zero");
+
+        // One config frame plus one frame per 8-byte chunk.
+        assert_eq!(frames.len(), 1 + data.len().div_ceil(8));
+        assert_eq!(frames[0].index, 0);
+        assert_eq!(frames[1].index, 1);
+        assert!(frames.iter().all(|f| f.total == frames[0].total));
+    }
+
+    #[test]
+    fn rejects_zero_chunk_size() {
+        assert!(split_into_frames(1, b"data", 0).is_err());
+    }
+
+    #[test]
+    fn frame_interval_matches_fps() {
+        assert_eq!(frame_interval(10), Duration::from_millis(100));
+        assert_eq!(frame_interval(1), Duration::from_millis(1000));
+    }
+}
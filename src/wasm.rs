@@ -0,0 +1,173 @@
+//! `wasm32` `GenerationBackend`: `std::thread::spawn`/`JoinHandle` don't
+//! exist on this target, so generation happens in a Web Worker instead.
+//! Requests are posted to the worker and pixel buffers come back through
+//! its `onmessage`, driving the same `DynPixmapCallbackFn` that the native
+//! backend uses, via Slint's event loop.
+use std::rc::Rc;
+
+use crossbeam_channel::{bounded, Sender};
+use fast_qr::ECL;
+use js_sys::{Object, Reflect, Uint8Array};
+use log::error;
+use slint::{Rgba8Pixel, SharedPixelBuffer};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{MessageEvent, Worker};
+
+use crate::{Content, ControlMessage, DynPixmapCallbackFn, GenerationBackend, PixelMapResult, QrGenerationRequest};
+
+/// Bundled alongside the page and loaded by [`Worker::new`] (see
+/// `web/qr_generation_worker.js`); it loads the wasm module this crate
+/// compiles to and forwards messages to [`crate::worker_entry`], which runs
+/// the same `fast_qr` pipeline as the native backend, just inside the
+/// worker thread the browser gives us instead of one we spawn ourselves.
+const WORKER_SCRIPT_URL: &str = "./qr_generation_worker.js";
+
+#[derive(Debug)]
+pub struct BackgroundThreadCommunicator {
+    worker: Worker,
+    request_sender: Rc<Sender<QrGenerationRequest>>,
+    control_sender: Rc<Sender<ControlMessage>>,
+    // Keeps the `onmessage` closure alive for as long as the worker is.
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+impl GenerationBackend for BackgroundThreadCommunicator {
+    fn new_thread(callback_function: Box<DynPixmapCallbackFn>) -> Self {
+        let worker = Worker::new(WORKER_SCRIPT_URL).expect("failed to start QR generation worker");
+
+        let on_message = Closure::wrap(Box::new(move |event: MessageEvent| {
+            let result = decode_worker_response(event.data());
+            if let Err(e) = callback_function(result) {
+                error!("Error while calling display callback: {}", e.to_string());
+            }
+        }) as Box<dyn FnMut(MessageEvent)>);
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        // Requests and control messages still flow through the same
+        // channel types the native backend uses; a task on the local
+        // executor drains them and forwards each one to the worker via
+        // `postMessage`, since the worker itself can't be sent a
+        // `crossbeam_channel::Receiver`.
+        let (request_tx, request_rx) = bounded::<QrGenerationRequest>(1);
+        let (control_tx, control_rx) = bounded::<ControlMessage>(16);
+
+        let forward_worker = worker.clone();
+        spawn_local(async move {
+            loop {
+                if let Ok(req) = request_rx.try_recv() {
+                    let _ = forward_worker.post_message(&encode_request(&req));
+                }
+                if let Ok(msg) = control_rx.try_recv() {
+                    let stop = msg == ControlMessage::Shutdown;
+                    let _ = forward_worker.post_message(&encode_control(msg));
+                    if stop {
+                        break;
+                    }
+                }
+                gloo_timers::future::TimeoutFuture::new(0).await;
+            }
+        });
+
+        BackgroundThreadCommunicator {
+            worker,
+            request_sender: Rc::new(request_tx),
+            control_sender: Rc::new(control_tx),
+            _on_message: on_message,
+        }
+    }
+
+    fn get_weak_sender(&self) -> std::rc::Weak<Sender<QrGenerationRequest>> {
+        Rc::downgrade(&self.request_sender)
+    }
+
+    fn get_weak_control_sender(&self) -> std::rc::Weak<Sender<ControlMessage>> {
+        Rc::downgrade(&self.control_sender)
+    }
+
+    fn stop(self) {
+        let _ = self.control_sender.send(ControlMessage::Shutdown);
+        drop(self.request_sender);
+        drop(self.control_sender);
+        self.worker.terminate();
+    }
+}
+
+/// Encodes a request as a plain JS object the worker script can read with
+/// `event.data.kind`/`.data`/`.chunkSize`/`.fps`/`.correctionLevel`/`.targetPx`.
+fn encode_request(request: &QrGenerationRequest) -> JsValue {
+    let obj = Object::new();
+    match &request.content {
+        Content::Static(data) => {
+            set(&obj, "kind", &"static".into());
+            set(&obj, "data", &data.into());
+        }
+        Content::Streamed {
+            data,
+            chunk_size,
+            fps,
+        } => {
+            set(&obj, "kind", &"streamed".into());
+            set(&obj, "data", &Uint8Array::from(data.as_slice()));
+            set(&obj, "chunkSize", &(*chunk_size as u32).into());
+            set(&obj, "fps", &(*fps).into());
+        }
+    }
+    if let Some(ecl) = request.correction_level {
+        set(&obj, "correctionLevel", &ecl_label(ecl).into());
+    }
+    if let Some(target_px) = request.target_px {
+        set(&obj, "targetPx", &target_px.into());
+    }
+    obj.into()
+}
+
+fn encode_control(message: ControlMessage) -> JsValue {
+    let label = match message {
+        ControlMessage::Pause => "pause",
+        ControlMessage::Resume => "resume",
+        ControlMessage::Cancel => "cancel",
+        ControlMessage::Shutdown => "shutdown",
+    };
+    JsValue::from_str(label)
+}
+
+fn ecl_label(ecl: ECL) -> &'static str {
+    match ecl {
+        ECL::L => "L",
+        ECL::M => "M",
+        ECL::Q => "Q",
+        ECL::H => "H",
+    }
+}
+
+fn set(obj: &Object, key: &str, value: &JsValue) {
+    let _ = Reflect::set(obj, &JsValue::from_str(key), value);
+}
+
+/// Reconstructs a [`PixelMapResult`] from the worker's `{error}` or
+/// `{width, height, pixels}` response object.
+fn decode_worker_response(data: JsValue) -> PixelMapResult {
+    let error = Reflect::get(&data, &JsValue::from_str("error")).ok();
+    if let Some(error) = error.and_then(|v| v.as_string()) {
+        return Err(error);
+    }
+
+    let get_u32 = |key: &str| {
+        Reflect::get(&data, &JsValue::from_str(key))
+            .ok()
+            .and_then(|v| v.as_f64())
+            .map(|v| v as u32)
+    };
+
+    let width = get_u32("width").ok_or("Worker response missing width")?;
+    let height = get_u32("height").ok_or("Worker response missing height")?;
+    let pixels = Reflect::get(&data, &JsValue::from_str("pixels"))
+        .map_err(|_| "Worker response missing pixels".to_string())?;
+    let pixels = Uint8Array::new(&pixels).to_vec();
+
+    Ok(SharedPixelBuffer::<Rgba8Pixel>::clone_from_slice(
+        &pixels, width, height,
+    ))
+}
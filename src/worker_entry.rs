@@ -0,0 +1,163 @@
+//! The `wasm32` Web Worker side of [`crate::wasm`]: compiled into the same
+//! wasm module as the rest of the crate and loaded by
+//! `qr_generation_worker.js` inside the worker's global scope. Decodes the
+//! request `wasm.rs::encode_request` posted, renders it with the same
+//! pipeline `native.rs` uses, and hands the result back as a plain JS
+//! object for the worker script to `postMessage`.
+use fast_qr::ECL;
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use crate::{new_qr_code_image, render_pixmap, stream, Content, PixelMapResult};
+
+/// Arbitrary fixed id: each worker only ever plays one stream at a time, so
+/// frames never need to be told apart by stream.
+const WORKER_STREAM_ID: u32 = 1;
+
+/// Renders a request posted by `wasm.rs::encode_request` and returns either
+/// a `{width, height, pixels}` object (static content), a `{frames, fps}`
+/// object whose `frames` are each such objects (streamed content, paced by
+/// the worker script itself), or an `{error}` object.
+#[wasm_bindgen]
+pub fn handle_worker_request(message: JsValue) -> JsValue {
+    match decode_request(&message) {
+        Ok(Request::Static {
+            content,
+            correction_level,
+            target_px,
+        }) => encode_frame(
+            new_qr_code_image(content, correction_level).map(|code| render_pixmap(code, target_px)),
+        ),
+        Ok(Request::Streamed {
+            data,
+            chunk_size,
+            fps,
+            correction_level,
+            target_px,
+        }) => encode_stream(&data, chunk_size, fps, correction_level, target_px),
+        Err(err) => encode_error(&err),
+    }
+}
+
+enum Request {
+    Static {
+        content: Content,
+        correction_level: Option<ECL>,
+        target_px: Option<u32>,
+    },
+    Streamed {
+        data: Vec<u8>,
+        chunk_size: usize,
+        fps: u32,
+        correction_level: Option<ECL>,
+        target_px: Option<u32>,
+    },
+}
+
+fn decode_request(message: &JsValue) -> Result<Request, String> {
+    let kind = get_string(message, "kind").ok_or("Worker request missing kind")?;
+    let correction_level =
+        get_string(message, "correctionLevel").and_then(|label| ecl_from_label(&label));
+    let target_px = get_u32(message, "targetPx");
+
+    match kind.as_str() {
+        "static" => {
+            let data = get_string(message, "data").ok_or("Worker request missing data")?;
+            Ok(Request::Static {
+                content: Content::Static(data),
+                correction_level,
+                target_px,
+            })
+        }
+        "streamed" => {
+            let data = Reflect::get(message, &JsValue::from_str("data"))
+                .map_err(|_| "Worker request missing data".to_string())?;
+            let data = Uint8Array::new(&data).to_vec();
+            let chunk_size =
+                get_u32(message, "chunkSize").ok_or("Worker request missing chunkSize")? as usize;
+            let fps = get_u32(message, "fps").ok_or("Worker request missing fps")?;
+            Ok(Request::Streamed {
+                data,
+                chunk_size,
+                fps,
+                correction_level,
+                target_px,
+            })
+        }
+        other => Err(format!("Unknown worker request kind: {other}")),
+    }
+}
+
+/// Renders every frame of a stream up front and returns them as a `{frames,
+/// fps}` object; the worker script paces `postMessage` calls itself with
+/// `setInterval` rather than calling back into wasm once per frame.
+fn encode_stream(
+    data: &[u8],
+    chunk_size: usize,
+    fps: u32,
+    correction_level: Option<ECL>,
+    target_px: Option<u32>,
+) -> JsValue {
+    let frames = match stream::split_into_frames(WORKER_STREAM_ID, data, chunk_size) {
+        Ok(frames) => frames,
+        Err(err) => return encode_error(&err),
+    };
+
+    let rendered = Array::new();
+    for frame in frames {
+        let result = new_qr_code_image(Content::Static(frame.text), correction_level)
+            .map(|code| render_pixmap(code, target_px));
+        rendered.push(&encode_frame(result));
+    }
+
+    let obj = Object::new();
+    set(&obj, "frames", &rendered);
+    set(&obj, "fps", &fps.into());
+    obj.into()
+}
+
+fn encode_frame(result: PixelMapResult) -> JsValue {
+    match result {
+        Ok(pixmap) => {
+            let obj = Object::new();
+            set(&obj, "width", &pixmap.width().into());
+            set(&obj, "height", &pixmap.height().into());
+            set(&obj, "pixels", &Uint8Array::from(pixmap.as_bytes()));
+            obj.into()
+        }
+        Err(err) => encode_error(&err),
+    }
+}
+
+fn encode_error(message: &str) -> JsValue {
+    let obj = Object::new();
+    set(&obj, "error", &message.into());
+    obj.into()
+}
+
+fn set(obj: &Object, key: &str, value: &JsValue) {
+    let _ = Reflect::set(obj, &JsValue::from_str(key), value);
+}
+
+fn get_string(obj: &JsValue, key: &str) -> Option<String> {
+    Reflect::get(obj, &JsValue::from_str(key))
+        .ok()
+        .and_then(|v| v.as_string())
+}
+
+fn get_u32(obj: &JsValue, key: &str) -> Option<u32> {
+    Reflect::get(obj, &JsValue::from_str(key))
+        .ok()
+        .and_then(|v| v.as_f64())
+        .map(|v| v as u32)
+}
+
+fn ecl_from_label(label: &str) -> Option<ECL> {
+    match label {
+        "L" => Some(ECL::L),
+        "M" => Some(ECL::M),
+        "Q" => Some(ECL::Q),
+        "H" => Some(ECL::H),
+        _ => None,
+    }
+}